@@ -0,0 +1,224 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A container-backed counterpart to `test_utils::network::setup_network_and_wallet`,
+//! for tests that want to exercise the real validator/full-node binaries and
+//! gossip/RPC stack instead of the in-process `swarm`. Gated behind the
+//! `testcontainers` feature so the in-process harness stays the default.
+
+use std::{path::Path, time::Duration};
+
+use anyhow::{anyhow, Context, Result};
+use sui_config::SUI_CLIENT_CONFIG;
+use tempfile::TempDir;
+use testcontainers::core::Mount;
+use testcontainers::runners::AsyncRunner;
+use testcontainers::ContainerAsync;
+use testcontainers::{core::WaitFor, GenericImage, ImageExt};
+use test_utils::network::WalletContext;
+
+const DEFAULT_IMAGE_TAG: &str = "latest";
+const DEFAULT_VALIDATOR_COUNT: usize = 4;
+const FULLNODE_RPC_CONTAINER_PORT: u16 = 9000;
+const GENESIS_MOUNT: &str = "/genesis";
+
+/// Shape of the containerized network: how many validators to run and which
+/// image tag to pull them from. The fullnode's RPC port is always an
+/// ephemeral host port assigned by Docker (read back via
+/// `get_host_port_ipv4`) rather than a fixed one, so multiple harness
+/// instances can run concurrently without colliding on the host.
+pub struct ContainerNetworkConfig {
+    pub validator_count: usize,
+    pub image_tag: String,
+}
+
+impl Default for ContainerNetworkConfig {
+    fn default() -> Self {
+        Self {
+            validator_count: DEFAULT_VALIDATOR_COUNT,
+            image_tag: DEFAULT_IMAGE_TAG.to_string(),
+        }
+    }
+}
+
+/// Owns the running validator and fullnode containers, the bridge network
+/// they share, and the genesis working directory mounted into all of them.
+/// Tearing this down (including on test panic) stops and removes every
+/// container.
+pub struct ContainerSwarm {
+    _validators: Vec<ContainerAsync<GenericImage>>,
+    _fullnode: ContainerAsync<GenericImage>,
+    genesis_dir: TempDir,
+}
+
+impl ContainerSwarm {
+    /// Directory holding the wallet config and keystore that `sui genesis`
+    /// wrote for this network, laid out the same way the in-process
+    /// `swarm.dir()` is, so call sites don't need to branch on which
+    /// harness produced the swarm.
+    pub fn dir(&self) -> &Path {
+        self.genesis_dir.path()
+    }
+}
+
+/// Runs `sui genesis` once, in its own short-lived container, so every
+/// validator and the fullnode start from the same committee and key
+/// material instead of each generating their own (which would leave them
+/// unable to agree on anything). `--ips` pins each validator's advertised
+/// address to its container's Docker-network hostname, which is resolvable
+/// by every other container on `network_name` -- without it, genesis would
+/// advertise `127.0.0.1`, which means something different inside each
+/// container.
+async fn run_genesis(
+    config: &ContainerNetworkConfig,
+    genesis_dir: &Path,
+    network_name: &str,
+) -> Result<()> {
+    let validator_ips = (0..config.validator_count)
+        .map(|i| format!("sui-validator-{i}"))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    GenericImage::new("mysten/sui-tools", &config.image_tag)
+        .with_wait_for(WaitFor::message_on_stdout("Genesis completed"))
+        .with_network(network_name)
+        .with_mount(Mount::bind_mount(
+            genesis_dir.to_string_lossy().to_string(),
+            GENESIS_MOUNT,
+        ))
+        .with_cmd([
+            "genesis",
+            "--working-dir",
+            GENESIS_MOUNT,
+            "--num-validators",
+            &config.validator_count.to_string(),
+            "--ips",
+            &validator_ips,
+        ])
+        .start()
+        .await
+        .context("failed to run genesis")?;
+    Ok(())
+}
+
+/// Points `client.yaml`'s active RPC endpoint at the fullnode's
+/// host-mapped port, so the `WalletContext` this harness returns -- and any
+/// `FileBasedKeystore::load_or_create` a test layers on top of it -- talks
+/// to the container network rather than whatever `sui genesis` defaulted to.
+///
+/// `rpc` lives nested under the `envs` entry matching `active_env`, not at
+/// the top level, so this parses the file as YAML and patches that specific
+/// entry's field rather than rewriting any line that happens to start with
+/// `rpc:` (which would also corrupt unrelated `envs` entries).
+fn patch_client_config_rpc(client_config: &Path, rpc_url: &str) -> Result<()> {
+    let contents = std::fs::read_to_string(client_config)
+        .with_context(|| format!("failed to read {}", client_config.display()))?;
+    let mut doc: serde_yaml::Value = serde_yaml::from_str(&contents)
+        .with_context(|| format!("failed to parse {} as YAML", client_config.display()))?;
+
+    let active_env = doc
+        .get("active_env")
+        .and_then(serde_yaml::Value::as_str)
+        .context("client config has no active_env")?
+        .to_owned();
+
+    let envs = doc
+        .get_mut("envs")
+        .and_then(serde_yaml::Value::as_sequence_mut)
+        .context("client config has no envs list")?;
+    let active = envs
+        .iter_mut()
+        .find(|env| env.get("alias").and_then(serde_yaml::Value::as_str) == Some(active_env.as_str()))
+        .with_context(|| format!("no envs entry aliased {active_env} in client config"))?;
+    let active = active
+        .as_mapping_mut()
+        .context("active envs entry is not a mapping")?;
+    active.insert(
+        serde_yaml::Value::String("rpc".to_string()),
+        serde_yaml::Value::String(rpc_url.to_string()),
+    );
+
+    let patched = serde_yaml::to_string(&doc)
+        .with_context(|| format!("failed to re-serialize {}", client_config.display()))?;
+    std::fs::write(client_config, patched)
+        .with_context(|| format!("failed to patch {}", client_config.display()))
+}
+
+/// Starts `config.validator_count` validators and one fullnode as Docker
+/// containers on a shared bridge network, all booted from one `sui
+/// genesis` run so they form a single network, waits for the fullnode's
+/// readiness endpoint, and returns a [`WalletContext`] pointed at its RPC --
+/// mirroring `test_utils::network::setup_network_and_wallet` so callers can
+/// swap one for the other behind a feature flag.
+pub async fn setup_network_and_wallet_with_config(
+    config: ContainerNetworkConfig,
+) -> Result<(ContainerSwarm, WalletContext, ())> {
+    let network_name = format!("sui-benchmark-{}", uuid::Uuid::new_v4());
+    let genesis_dir = TempDir::new().context("failed to create genesis working dir")?;
+
+    run_genesis(&config, genesis_dir.path(), &network_name).await?;
+
+    let mut validators = Vec::with_capacity(config.validator_count);
+    for i in 0..config.validator_count {
+        let container = GenericImage::new("mysten/sui-node", &config.image_tag)
+            .with_wait_for(WaitFor::message_on_stdout("narwhal committee ready"))
+            .with_network(&network_name)
+            .with_container_name(format!("sui-validator-{i}"))
+            .with_mount(Mount::bind_mount(
+                genesis_dir.path().to_string_lossy().to_string(),
+                GENESIS_MOUNT,
+            ))
+            .with_cmd(["--config-path", &format!("{GENESIS_MOUNT}/validator{i}.yaml")])
+            .start()
+            .await
+            .with_context(|| format!("failed to start validator {i}"))?;
+        validators.push(container);
+    }
+
+    let fullnode = GenericImage::new("mysten/sui-node", &config.image_tag)
+        .with_wait_for(WaitFor::Healthcheck)
+        .with_network(&network_name)
+        .with_container_name("sui-fullnode")
+        .with_mount(Mount::bind_mount(
+            genesis_dir.path().to_string_lossy().to_string(),
+            GENESIS_MOUNT,
+        ))
+        .with_cmd(["--config-path", &format!("{GENESIS_MOUNT}/fullnode.yaml")])
+        .with_exposed_port(FULLNODE_RPC_CONTAINER_PORT.into())
+        .start()
+        .await
+        .context("failed to start fullnode")?;
+
+    // Let Docker pick the host port rather than pinning it, so two harness
+    // instances can run side by side; the actual assignment is read back
+    // here.
+    let rpc_port = fullnode
+        .get_host_port_ipv4(FULLNODE_RPC_CONTAINER_PORT)
+        .await
+        .context("fullnode did not expose an RPC port")?;
+    let rpc_url = format!("http://127.0.0.1:{rpc_port}");
+
+    let client_config = genesis_dir.path().join(SUI_CLIENT_CONFIG);
+    patch_client_config_rpc(&client_config, &rpc_url)?;
+
+    let context = WalletContext::new(&client_config, Some(Duration::from_secs(60)), None)
+        .await
+        .map_err(|e| anyhow!("failed to build wallet context from {rpc_url}: {e}"))?;
+
+    Ok((
+        ContainerSwarm {
+            _validators: validators,
+            _fullnode: fullnode,
+            genesis_dir,
+        },
+        context,
+        (),
+    ))
+}
+
+/// `setup_network_and_wallet_with_config` with the default topology, mirroring
+/// the `(swarm, context, _)` signature of `test_utils::network::setup_network_and_wallet`
+/// so existing tests can switch harnesses with nothing but a feature flag.
+pub async fn setup_network_and_wallet() -> Result<(ContainerSwarm, WalletContext, ())> {
+    setup_network_and_wallet_with_config(ContainerNetworkConfig::default()).await
+}
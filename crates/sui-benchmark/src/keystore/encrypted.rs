@@ -0,0 +1,246 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use argon2::{Argon2, Params};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sui_types::{
+    base_types::SuiAddress,
+    crypto::{EncodeDecodeBase64, PublicKey, Signature, SuiKeyPair},
+};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{
+    keystore::{Keystore, KeystoreError},
+    workloads::sign_with_scheme,
+};
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// One encrypted key on disk. Key material is only ever persisted to disk as
+/// `ciphertext`; everything needed to decrypt it given the right password
+/// travels alongside it in plaintext, including its own salt -- each entry is
+/// encrypted under a key independently derived from the operator's
+/// password, so two entries never share a derived key even if they share a
+/// password.
+#[derive(Clone, Serialize, Deserialize)]
+struct EncryptedEntry {
+    address: SuiAddress,
+    public_key: PublicKey,
+    salt: [u8; SALT_LEN],
+    nonce: [u8; NONCE_LEN],
+    ciphertext: Vec<u8>,
+}
+
+/// On-disk container for every encrypted entry, analogous to the plaintext
+/// key list `FileBasedKeystore` reads and writes.
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct EncryptedKeystoreFile {
+    entries: Vec<EncryptedEntry>,
+}
+
+/// Decrypted key material kept only as long as the keystore is unlocked,
+/// cached as its zeroizing base64 encoding rather than a live `SuiKeyPair` --
+/// `SuiKeyPair` has no `Zeroize` impl of its own to lean on, so wiping the
+/// encoding on evict/drop is how this cache actually guarantees no key
+/// material survives a `lock()` or a dropped keystore, rather than just
+/// asserting it.
+#[derive(Default)]
+struct KeyCache {
+    entries: Mutex<HashMap<SuiAddress, Zeroizing<String>>>,
+}
+
+impl KeyCache {
+    /// Signs `msg` for `address`, decrypting via `decrypt` only on a cache
+    /// miss. The `SuiKeyPair` this reconstructs from the cached encoding is
+    /// never itself stored -- only the zeroizing encoding is -- so it lives
+    /// only for the duration of this call.
+    fn sign(
+        &self,
+        address: SuiAddress,
+        decrypt: impl FnOnce() -> Result<SuiKeyPair, KeystoreError>,
+        msg: &[u8],
+    ) -> Result<Signature, KeystoreError> {
+        let mut entries = self.entries.lock().unwrap();
+        let encoded = match entries.get(&address) {
+            Some(encoded) => encoded.clone(),
+            None => {
+                let key_pair = decrypt()?;
+                let encoded = Zeroizing::new(key_pair.encode_base64());
+                entries.insert(address, encoded.clone());
+                encoded
+            }
+        };
+        drop(entries);
+        let key_pair = SuiKeyPair::decode_base64(&encoded)
+            .map_err(|e| KeystoreError::Device(format!("corrupt cached key: {e}")))?;
+        Ok(sign_with_scheme(&key_pair, msg))
+    }
+
+    fn evict_all(&self) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.clear();
+    }
+}
+
+/// An encrypted-at-rest [`Keystore`]. Every entry is encrypted with
+/// AES-256-GCM under a key derived, per entry, from the operator's password
+/// and that entry's own salt via Argon2id, so the file on disk is safe to
+/// leave in place; decrypted keys only ever exist transiently in
+/// [`KeyCache`], which is zeroized on `lock()` and on drop.
+pub struct EncryptedKeystore {
+    path: PathBuf,
+    file: EncryptedKeystoreFile,
+    password: Mutex<Option<Zeroizing<String>>>,
+    cache: KeyCache,
+}
+
+impl EncryptedKeystore {
+    pub fn load_or_create(path: &Path) -> Result<Self, KeystoreError> {
+        let file = if path.exists() {
+            let bytes = std::fs::read(path)?;
+            serde_json::from_slice(&bytes)
+                .map_err(|e| KeystoreError::Device(format!("corrupt keystore file: {e}")))?
+        } else {
+            EncryptedKeystoreFile::default()
+        };
+        Ok(Self {
+            path: path.to_owned(),
+            file,
+            password: Mutex::new(None),
+            cache: KeyCache::default(),
+        })
+    }
+
+    /// Remembers `password` so subsequent `sign` calls can decrypt entries
+    /// on demand, deriving a fresh key per entry from its own salt. Does
+    /// not verify the password up front; an incorrect password simply
+    /// fails to decrypt (and is reported) the first time a key is needed.
+    pub fn unlock(&self, password: &str) {
+        *self.password.lock().unwrap() = Some(Zeroizing::new(password.to_string()));
+    }
+
+    /// Forgets the remembered password and evicts every decrypted keypair
+    /// from the in-memory cache, zeroizing each one.
+    pub fn lock(&self) {
+        self.cache.evict_all();
+        *self.password.lock().unwrap() = None;
+    }
+
+    /// Encrypts `key_pair` under a freshly minted salt and nonce, appends it
+    /// to the keystore and persists the file. `password` must match whatever
+    /// password future `unlock()` calls will use to decrypt it again.
+    pub fn import(&mut self, key_pair: &SuiKeyPair, password: &str) -> Result<SuiAddress, KeystoreError> {
+        let public_key = key_pair.public();
+        let address = SuiAddress::from(&public_key);
+
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let mut nonce_bytes = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce_bytes);
+
+        let entry_key = derive_entry_key(password, &salt)?;
+        let cipher = Aes256Gcm::new_from_slice(entry_key.as_slice())
+            .map_err(|e| KeystoreError::Device(e.to_string()))?;
+        let nonce = Nonce::from_slice(&nonce_bytes);
+        let plaintext = key_pair.encode_base64();
+        let ciphertext = cipher
+            .encrypt(nonce, plaintext.as_bytes())
+            .map_err(|e| KeystoreError::Device(e.to_string()))?;
+
+        self.file.entries.push(EncryptedEntry {
+            address,
+            public_key,
+            salt,
+            nonce: nonce_bytes,
+            ciphertext,
+        });
+        self.save()?;
+        Ok(address)
+    }
+
+    fn save(&self) -> Result<(), KeystoreError> {
+        let bytes = serde_json::to_vec_pretty(&self.file)
+            .map_err(|e| KeystoreError::Device(format!("failed to serialize keystore: {e}")))?;
+        std::fs::write(&self.path, bytes)?;
+        Ok(())
+    }
+
+    fn decrypt(&self, entry: &EncryptedEntry) -> Result<SuiKeyPair, KeystoreError> {
+        let password = self.password.lock().unwrap();
+        let password = password.as_ref().ok_or(KeystoreError::DeviceLocked)?;
+        let entry_key = derive_entry_key(password, &entry.salt)?;
+        let cipher = Aes256Gcm::new_from_slice(entry_key.as_slice())
+            .map_err(|e| KeystoreError::Device(e.to_string()))?;
+        let nonce = Nonce::from_slice(&entry.nonce);
+        let plaintext = Zeroizing::new(
+            cipher
+                .decrypt(nonce, entry.ciphertext.as_slice())
+                .map_err(|_| KeystoreError::Device("incorrect password or corrupt entry".into()))?,
+        );
+        let encoded = std::str::from_utf8(&plaintext)
+            .map_err(|e| KeystoreError::Device(format!("corrupt decrypted key: {e}")))?;
+        SuiKeyPair::decode_base64(encoded)
+            .map_err(|e| KeystoreError::Device(format!("corrupt decrypted key: {e}")))
+    }
+}
+
+/// Derives a 256-bit AES key from `password` and `salt` via Argon2id. Called
+/// once per entry -- with that entry's own salt -- rather than once per
+/// keystore, so entries never share a derived key.
+fn derive_entry_key(password: &str, salt: &[u8; SALT_LEN]) -> Result<Zeroizing<[u8; 32]>, KeystoreError> {
+    let argon2 = Argon2::new(
+        argon2::Algorithm::Argon2id,
+        argon2::Version::V0x13,
+        Params::default(),
+    );
+    let mut key = Zeroizing::new([0u8; 32]);
+    argon2
+        .hash_password_into(password.as_bytes(), salt, key.as_mut_slice())
+        .map_err(|e| KeystoreError::Device(e.to_string()))?;
+    Ok(key)
+}
+
+impl Drop for EncryptedKeystore {
+    fn drop(&mut self) {
+        self.lock();
+        if let Some(mut password) = self.password.lock().unwrap().take() {
+            password.zeroize();
+        }
+    }
+}
+
+impl Keystore for EncryptedKeystore {
+    fn addresses(&self) -> Vec<SuiAddress> {
+        self.file.entries.iter().map(|e| e.address).collect()
+    }
+
+    fn public_key_for_address(&self, address: &SuiAddress) -> Option<PublicKey> {
+        self.file
+            .entries
+            .iter()
+            .find(|e| &e.address == address)
+            .map(|e| e.public_key.clone())
+    }
+
+    fn sign(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, KeystoreError> {
+        let entry = self
+            .file
+            .entries
+            .iter()
+            .find(|e| &e.address == address)
+            .ok_or(KeystoreError::UnknownAddress(*address))?;
+        self.cache.sign(*address, || self.decrypt(entry), msg)
+    }
+}
@@ -0,0 +1,44 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::Path;
+
+use sui_sdk::crypto::FileBasedKeystore;
+use sui_types::{
+    base_types::SuiAddress,
+    crypto::{PublicKey, Signature},
+};
+
+use crate::{
+    keystore::{Keystore, KeystoreError},
+    workloads::sign_with_scheme,
+};
+
+pub fn open(path: &Path) -> Result<FileBasedKeystore, KeystoreError> {
+    Ok(FileBasedKeystore::load_or_create(path)?)
+}
+
+impl Keystore for FileBasedKeystore {
+    fn addresses(&self) -> Vec<SuiAddress> {
+        self.key_pairs()
+            .into_iter()
+            .map(|kp| (&kp.public()).into())
+            .collect()
+    }
+
+    fn public_key_for_address(&self, address: &SuiAddress) -> Option<PublicKey> {
+        self.key_pairs()
+            .into_iter()
+            .map(|kp| kp.public())
+            .find(|pk| &SuiAddress::from(pk) == address)
+    }
+
+    fn sign(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, KeystoreError> {
+        let key_pair = self
+            .key_pairs()
+            .into_iter()
+            .find(|kp| &SuiAddress::from(&kp.public()) == address)
+            .ok_or(KeystoreError::UnknownAddress(*address))?;
+        Ok(sign_with_scheme(key_pair, msg))
+    }
+}
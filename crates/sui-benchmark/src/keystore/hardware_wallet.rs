@@ -0,0 +1,173 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use fastcrypto::{ed25519::Ed25519PublicKey, traits::ToFromBytes};
+use hidapi::{HidApi, HidDevice};
+use sui_types::{
+    base_types::SuiAddress,
+    crypto::{PublicKey, Signature, SignatureScheme},
+};
+
+use crate::keystore::{Keystore, KeystoreError};
+
+/// Vendor/product IDs of the Trezor- and Ledger-style devices we know how to
+/// talk to. Both speak the same simple HID framing for the subset of
+/// commands we need (get public key, get lock status, sign digest).
+const SUPPORTED_DEVICES: &[(u16, u16)] = &[
+    (0x1209, 0x53c1), // Trezor-compatible
+    (0x2c97, 0x0001), // Ledger-compatible
+];
+
+const HID_REPORT_SIZE: usize = 64;
+const HID_READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+const CMD_GET_PUBLIC_KEY: u8 = 0x01;
+const CMD_GET_STATUS: u8 = 0x02;
+const CMD_SIGN: u8 = 0x03;
+
+const STATUS_UNLOCKED: u8 = 0x00;
+
+/// One enumerated device, plus the Sui address/public key it reported for
+/// its Ed25519 derivation path. The device is re-opened for every sign so we
+/// never hold a private key in process memory -- there is none to hold.
+struct Device {
+    path: std::ffi::CString,
+    address: SuiAddress,
+    public_key: PublicKey,
+}
+
+/// A [`Keystore`] backed by USB HID hardware wallets: the private key never
+/// leaves the device, and signing forwards the transaction digest to it.
+pub struct HardwareWalletKeystore {
+    api: HidApi,
+    devices: Vec<Device>,
+}
+
+impl HardwareWalletKeystore {
+    /// Enumerates connected, supported devices and derives each one's Sui
+    /// address from its Ed25519 path. Returns an empty keystore (not an
+    /// error) if no supported device is plugged in.
+    pub fn enumerate() -> Result<Self, KeystoreError> {
+        let api = HidApi::new().map_err(|e| KeystoreError::Device(e.to_string()))?;
+        let mut devices = Vec::new();
+        for info in api.device_list() {
+            if !SUPPORTED_DEVICES.contains(&(info.vendor_id(), info.product_id())) {
+                continue;
+            }
+            let handle = info
+                .open_device(&api)
+                .map_err(|e| KeystoreError::Device(e.to_string()))?;
+            let public_key = request_public_key(&handle)?;
+            devices.push(Device {
+                path: info.path().to_owned(),
+                address: (&public_key).into(),
+                public_key,
+            });
+        }
+        Ok(Self { api, devices })
+    }
+
+    fn open(&self, device: &Device) -> Result<HidDevice, KeystoreError> {
+        self.api
+            .open_path(&device.path)
+            .map_err(|e| KeystoreError::Device(e.to_string()))
+    }
+}
+
+impl Keystore for HardwareWalletKeystore {
+    fn addresses(&self) -> Vec<SuiAddress> {
+        self.devices.iter().map(|d| d.address).collect()
+    }
+
+    fn public_key_for_address(&self, address: &SuiAddress) -> Option<PublicKey> {
+        self.devices
+            .iter()
+            .find(|d| &d.address == address)
+            .map(|d| d.public_key.clone())
+    }
+
+    fn sign(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, KeystoreError> {
+        let device = self
+            .devices
+            .iter()
+            .find(|d| &d.address == address)
+            .ok_or(KeystoreError::UnknownAddress(*address))?;
+        let handle = self.open(device)?;
+        if device_is_locked(&handle)? {
+            // Caller is expected to prompt for a PIN/passphrase on the
+            // device itself and retry; we never ask for it over HID.
+            return Err(KeystoreError::DeviceLocked);
+        }
+        request_signature(&handle, &device.public_key, msg)
+    }
+}
+
+/// Writes `cmd` and `payload` as a single HID report and reads back one
+/// report in response. Every command in this protocol fits in one report
+/// each way, so no chunking is needed.
+fn transact(device: &HidDevice, cmd: u8, payload: &[u8]) -> Result<Vec<u8>, KeystoreError> {
+    if payload.len() > HID_REPORT_SIZE - 1 {
+        return Err(KeystoreError::Device(format!(
+            "{}-byte payload does not fit in a {HID_REPORT_SIZE}-byte HID report",
+            payload.len()
+        )));
+    }
+    let mut report = [0u8; HID_REPORT_SIZE];
+    report[0] = cmd;
+    report[1..1 + payload.len()].copy_from_slice(payload);
+    device
+        .write(&report)
+        .map_err(|e| KeystoreError::Device(e.to_string()))?;
+
+    let mut response = [0u8; HID_REPORT_SIZE];
+    let read = device
+        .read_timeout(&mut response, HID_READ_TIMEOUT.as_millis() as i32)
+        .map_err(|e| KeystoreError::Device(e.to_string()))?;
+    if read == 0 {
+        return Err(KeystoreError::Device(
+            "device did not respond before the HID read timeout".to_string(),
+        ));
+    }
+    Ok(response[..read].to_vec())
+}
+
+fn request_public_key(device: &HidDevice) -> Result<PublicKey, KeystoreError> {
+    let response = transact(device, CMD_GET_PUBLIC_KEY, &[])?;
+    let bytes = response.get(..32).ok_or_else(|| {
+        KeystoreError::Device("GET_PUBLIC_KEY response shorter than 32 bytes".to_string())
+    })?;
+    let public_key = Ed25519PublicKey::from_bytes(bytes)
+        .map_err(|e| KeystoreError::Device(format!("malformed device public key: {e}")))?;
+    Ok(PublicKey::Ed25519(public_key))
+}
+
+fn device_is_locked(device: &HidDevice) -> Result<bool, KeystoreError> {
+    let response = transact(device, CMD_GET_STATUS, &[])?;
+    let status = *response
+        .first()
+        .ok_or_else(|| KeystoreError::Device("GET_STATUS response was empty".to_string()))?;
+    Ok(status != STATUS_UNLOCKED)
+}
+
+fn request_signature(
+    device: &HidDevice,
+    public_key: &PublicKey,
+    digest: &[u8],
+) -> Result<Signature, KeystoreError> {
+    let response = transact(device, CMD_SIGN, digest)?;
+    let raw_signature = response.get(..64).ok_or_else(|| {
+        KeystoreError::Device("SIGN response shorter than the 64-byte Ed25519 signature".to_string())
+    })?;
+
+    // Sui's flag||signature||public_key encoding, same as every other
+    // in-process signer produces; the device only ever supplies the raw
+    // Ed25519 signature bytes.
+    let mut framed = Vec::with_capacity(1 + 64 + 32);
+    framed.push(SignatureScheme::ED25519.flag());
+    framed.extend_from_slice(raw_signature);
+    framed.extend_from_slice(public_key.as_ref());
+    Signature::from_bytes(&framed)
+        .map_err(|e| KeystoreError::Device(format!("device produced an invalid signature: {e}")))
+}
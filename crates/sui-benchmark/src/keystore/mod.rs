@@ -0,0 +1,60 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+mod encrypted;
+mod file_based;
+mod hardware_wallet;
+
+use std::path::Path;
+
+pub use encrypted::EncryptedKeystore;
+pub use hardware_wallet::HardwareWalletKeystore;
+use sui_types::{base_types::SuiAddress, crypto::PublicKey, crypto::Signature};
+use thiserror::Error;
+
+/// Signing abstraction the benchmark (and, eventually, the CLI) program
+/// against, so that where the private key actually lives -- a plaintext
+/// file, an encrypted file, a hardware wallet -- is a startup choice rather
+/// than something baked into every call site that needs to sign.
+pub trait Keystore: Send + Sync {
+    /// All addresses this keystore can sign on behalf of.
+    fn addresses(&self) -> Vec<SuiAddress>;
+
+    /// The public key backing `address`, if this keystore holds it.
+    fn public_key_for_address(&self, address: &SuiAddress) -> Option<PublicKey>;
+
+    /// Signs `msg` on behalf of `address`. Backends whose key material is
+    /// not immediately available (e.g. a PIN-locked hardware wallet) return
+    /// [`KeystoreError::DeviceLocked`] so the caller can prompt and retry.
+    fn sign(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, KeystoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum KeystoreError {
+    #[error("no key for address {0}")]
+    UnknownAddress(SuiAddress),
+    #[error("device is locked; a PIN or passphrase is required before signing")]
+    DeviceLocked,
+    #[error("keystore backend error: {0}")]
+    Device(String),
+    #[error("keystore io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Which [`Keystore`] implementation to open at startup.
+pub enum KeystoreBackend {
+    /// A `sui_sdk::crypto::FileBasedKeystore` loaded from `path`.
+    File,
+    /// Every supported hardware wallet enumerated over USB HID; `path` is
+    /// not used.
+    HardwareWallet,
+}
+
+/// Opens the requested backend, giving the benchmark and CLI a single place
+/// to decide where signing keys come from.
+pub fn open(backend: KeystoreBackend, path: &Path) -> Result<Box<dyn Keystore>, KeystoreError> {
+    match backend {
+        KeystoreBackend::File => Ok(Box::new(file_based::open(path)?)),
+        KeystoreBackend::HardwareWallet => Ok(Box::new(HardwareWalletKeystore::enumerate()?)),
+    }
+}
@@ -0,0 +1,8 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(feature = "testcontainers")]
+pub mod container_network;
+pub mod drivers;
+pub mod keystore;
+pub mod workloads;
@@ -0,0 +1,9 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+mod workload;
+
+pub use workload::{
+    make_combination_workload, sign_with_scheme, CombinationWorkload, SignedTestTransaction,
+    TestTransactionKind, Workload,
+};
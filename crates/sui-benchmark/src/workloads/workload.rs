@@ -0,0 +1,167 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use sui_types::{
+    base_types::{ObjectID, SuiAddress},
+    crypto::{PublicKey, Signature, SuiKeyPair},
+};
+
+/// Which synthetic transaction a [`CombinationWorkload`] batch entry stands
+/// in for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TestTransactionKind {
+    SharedCounterIncrement,
+    TransferObject { to_account: u64 },
+}
+
+/// A single transaction produced by a workload, paired with the signature
+/// that authorizes it. Drivers take these as-is; workloads are responsible
+/// for producing a signature that a verifier will accept for the scheme the
+/// sender actually uses. `public_key` and `digest`
+/// travel with the signature so a driver can verify the round-trip itself
+/// instead of trusting that the workload signed correctly.
+pub struct SignedTestTransaction {
+    pub sender: SuiAddress,
+    pub gas_object_id: ObjectID,
+    pub kind: TestTransactionKind,
+    pub public_key: PublicKey,
+    pub digest: Vec<u8>,
+    pub signature: Signature,
+}
+
+/// Something that can keep producing synthetic load against a running
+/// network. Each workload owns the account(s) it drives transactions from,
+/// including their signing material, so the driver never needs to know which
+/// signature scheme is in play.
+pub trait Workload: Send + Sync {
+    /// Produce the next batch of signed transactions for this workload.
+    fn make_transactions(&self) -> Vec<SignedTestTransaction>;
+}
+
+/// Signs `msg` with `keypair`, dispatching on the concrete scheme so that
+/// Ed25519, Secp256k1 and Secp256r1 senders are all exercised under load
+/// rather than just Ed25519. Adding a new `SuiKeyPair` variant will fail to
+/// compile here until its signing path is wired up.
+pub fn sign_with_scheme(keypair: &SuiKeyPair, msg: &[u8]) -> Signature {
+    match keypair {
+        SuiKeyPair::Ed25519SuiKeyPair(kp) => Signature::new(kp, msg),
+        SuiKeyPair::Secp256k1SuiKeyPair(kp) => Signature::new(kp, msg),
+        SuiKeyPair::Secp256r1SuiKeyPair(kp) => Signature::new(kp, msg),
+    }
+}
+
+/// A workload that mixes transfers and shared-counter increments from a
+/// single funded account, signing every transaction with whatever scheme
+/// `keypair` happens to be rather than assuming Ed25519.
+pub struct CombinationWorkload {
+    target_qps: u64,
+    num_workers: u64,
+    in_flight_ratio: u64,
+    primary_gas_id: ObjectID,
+    owner: SuiAddress,
+    keypair: Arc<SuiKeyPair>,
+    num_transfer_accounts: u64,
+    shared_counter_weight: u32,
+    transfer_object_weight: u32,
+}
+
+impl CombinationWorkload {
+    /// How many transactions one call to `make_transactions` produces: the
+    /// number of requests in flight at once (`target_qps * in_flight_ratio`)
+    /// split evenly across workers, so each worker asks for its own share of
+    /// the target load rather than duplicating the whole thing.
+    fn batch_size(&self) -> u64 {
+        let in_flight = self.target_qps.saturating_mul(self.in_flight_ratio);
+        (in_flight / self.num_workers.max(1)).max(1)
+    }
+
+    /// Picks a transaction kind for batch position `index`, honoring the
+    /// configured mix between shared-counter increments and object
+    /// transfers. Transfers rotate through `num_transfer_accounts` distinct
+    /// recipients instead of always hitting the same one.
+    fn kind_for_index(&self, index: u64) -> TestTransactionKind {
+        let shared_weight = self.shared_counter_weight as u64;
+        let total_weight = (shared_weight + self.transfer_object_weight as u64).max(1);
+        let position = index % total_weight;
+        if position < shared_weight {
+            TestTransactionKind::SharedCounterIncrement
+        } else {
+            // Number the transfers among themselves, so the recipient rotates
+            // however the weights interleave with the account count.
+            let transfers_per_cycle = total_weight - shared_weight;
+            let transfer_ordinal =
+                (index / total_weight) * transfers_per_cycle + (position - shared_weight);
+            TestTransactionKind::TransferObject {
+                to_account: transfer_ordinal % self.num_transfer_accounts.max(1),
+            }
+        }
+    }
+}
+
+/// A synthetic stand-in for a real transaction digest, since this crate
+/// doesn't have a `TransactionData` builder available to it: enough to vary
+/// per batch entry and be signed/verified like a real digest would be.
+fn synthetic_digest(gas_object_id: ObjectID, kind: TestTransactionKind, sequence: u64) -> Vec<u8> {
+    let mut bytes = gas_object_id.to_vec();
+    match kind {
+        TestTransactionKind::SharedCounterIncrement => bytes.push(0),
+        TestTransactionKind::TransferObject { to_account } => {
+            bytes.push(1);
+            bytes.extend_from_slice(&to_account.to_le_bytes());
+        }
+    }
+    bytes.extend_from_slice(&sequence.to_le_bytes());
+    bytes
+}
+
+impl Workload for CombinationWorkload {
+    fn make_transactions(&self) -> Vec<SignedTestTransaction> {
+        (0..self.batch_size())
+            .map(|i| {
+                let kind = self.kind_for_index(i);
+                let digest = synthetic_digest(self.primary_gas_id, kind, i);
+                let signature = sign_with_scheme(&self.keypair, &digest);
+                SignedTestTransaction {
+                    sender: self.owner,
+                    gas_object_id: self.primary_gas_id,
+                    kind,
+                    public_key: self.keypair.public(),
+                    digest,
+                    signature,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Builds a [`CombinationWorkload`] driven by `keypair`, whatever signature
+/// scheme it happens to be. Previously this took an `Arc<Ed25519KeyPair>`
+/// directly and could only ever represent Ed25519 senders; validators need
+/// to be load-tested against mixed-scheme traffic, so callers now pass the
+/// `SuiKeyPair` enum and the workload signs according to its variant.
+#[allow(clippy::too_many_arguments)]
+pub fn make_combination_workload(
+    target_qps: u64,
+    num_workers: u64,
+    in_flight_ratio: u64,
+    primary_gas_id: ObjectID,
+    owner: SuiAddress,
+    keypair: Arc<SuiKeyPair>,
+    num_transfer_accounts: u64,
+    shared_counter_weight: u32,
+    transfer_object_weight: u32,
+) -> Box<dyn Workload> {
+    Box::new(CombinationWorkload {
+        target_qps,
+        num_workers,
+        in_flight_ratio,
+        primary_gas_id,
+        owner,
+        keypair,
+        num_transfer_accounts,
+        shared_counter_weight,
+        transfer_object_weight,
+    })
+}
@@ -2,18 +2,46 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::sync::Arc;
+use prometheus::Registry;
 use sui_config::SUI_CLIENT_CONFIG;
 use sui_sdk::crypto::FileBasedKeystore;
-use sui_types::{
-    base_types::SuiAddress,
-    crypto::{EncodeDecodeBase64, SuiKeyPair},
+use sui_types::crypto::{
+    Ed25519KeyPair, EncodeDecodeBase64, Secp256k1KeyPair, Secp256r1KeyPair, SuiKeyPair,
 };
-use test_utils::{messages::get_gas_object_with_wallet_context, network::setup_network_and_wallet};
+use sui_types::base_types::SuiAddress;
+use test_utils::messages::get_gas_object_with_wallet_context;
+#[cfg(not(feature = "testcontainers"))]
+use test_utils::network::setup_network_and_wallet;
 
-use sui_benchmark::{drivers::bench_driver::BenchDriver, workloads::make_combination_workload};
+use fastcrypto::traits::KeyPair as _;
+#[cfg(feature = "testcontainers")]
+use sui_benchmark::container_network::setup_network_and_wallet;
+use sui_benchmark::{
+    drivers::bench_driver::{BenchDriver, BenchmarkAggregator, FrameTransport, RoutingOutcome},
+    workloads::{make_combination_workload, sign_with_scheme},
+};
 
 use sui_macros::sim_test;
 
+/// Adapts `sui_simulator`'s fault-injecting transport to
+/// `sui_benchmark::drivers::bench_driver::FrameTransport`, so a sim run can
+/// hand `BenchDriver::run` a transport that actually consults packet loss,
+/// partition, bandwidth and jitter settings instead of routing unconditionally.
+#[cfg(msim)]
+struct SimFaultTransport(sui_simulator::configs::FaultInjectingTransport);
+
+#[cfg(msim)]
+impl FrameTransport for SimFaultTransport {
+    fn route(&self, from: u64, to: u64, payload_len: u64) -> RoutingOutcome {
+        match self.0.route(from, to, payload_len, std::time::Instant::now()) {
+            sui_simulator::configs::RoutingDecision::Drop => RoutingOutcome::Drop,
+            sui_simulator::configs::RoutingDecision::Deliver { delay } => {
+                RoutingOutcome::Deliver { delay }
+            }
+        }
+    }
+}
+
 #[sim_test]
 async fn test_simulated_load() {
     let (swarm, context, _) = setup_network_and_wallet().await.unwrap();
@@ -27,36 +55,81 @@ async fn test_simulated_load() {
 
     let sender: SuiAddress = (&public_key).into();
 
-    let ed_key_pair = match key_pair {
-        SuiKeyPair::Ed25519SuiKeyPair(kp) => kp,
-        _ => panic!(),
-    };
-
-    // we can't clone, but can ser/deser
-    let ed_key_pair = ed_key_pair.encode_base64();
-    let ed_key_pair = Arc::new(match SuiKeyPair::decode_base64(&ed_key_pair).unwrap() {
-        SuiKeyPair::Ed25519SuiKeyPair(x) => x,
-        _ => panic!("Unexpected keypair type"),
-    });
+    // we can't clone, but can ser/deser; this round-trips whatever scheme the
+    // keystore handed us, not just Ed25519.
+    let key_pair = Arc::new(SuiKeyPair::decode_base64(&key_pair.encode_base64()).unwrap());
 
     let gas = get_gas_object_with_wallet_context(&context, &sender)
         .await
         .expect("Expect {sender} to have at least one gas object");
 
-    let _combination_workload = make_combination_workload(
-        10,          // target_qps
-        10,          // num_workers
-        5,           // in_flight_ratio
-        gas.0,       // primary_gas_id
-        sender,      // owner
-        ed_key_pair, // keypair
-        1,           // num_transfer_accounts
-        1,           // shared_counter_weight
-        1,           // transfer_object_weight
+    let combination_workload = make_combination_workload(
+        10,       // target_qps
+        10,       // num_workers
+        5,        // in_flight_ratio
+        gas.0,    // primary_gas_id
+        sender,   // owner
+        key_pair, // keypair
+        1,        // num_transfer_accounts
+        1,        // shared_counter_weight
+        1,        // transfer_object_weight
     );
 
+    let stat_collection_interval = 10;
+    let registry = Registry::new();
     let driver = BenchDriver::new(stat_collection_interval);
-    driver.run(workloads, aggregator, &registry).await;
+    let aggregator = BenchmarkAggregator::new();
+
+    // Under msim, route every transaction through a fault-injecting
+    // transport configured with some WAN-ish packet loss, so this sim run
+    // actually experiences drops rather than bypassing fault injection
+    // entirely.
+    #[cfg(msim)]
+    let transport = SimFaultTransport(sui_simulator::configs::FaultInjectingTransport::new(
+        sui_simulator::configs::packet_loss(0.01),
+        std::time::Instant::now(),
+        42,
+    ));
+    #[cfg(msim)]
+    let transport: Option<&dyn FrameTransport> = Some(&transport);
+    #[cfg(not(msim))]
+    let transport: Option<&dyn FrameTransport> = None;
+
+    driver
+        .run(vec![combination_workload], &aggregator, &registry, transport)
+        .await;
+
+    // Every transaction the driver generated must have either been dropped
+    // by the transport or verified against its sender's public key -- never
+    // silently discarded.
+    assert!(aggregator.generated() > 0);
+    assert_eq!(aggregator.failed(), 0);
+    assert_eq!(
+        aggregator.verified() + aggregator.dropped(),
+        aggregator.generated()
+    );
 
     println!("OK");
 }
+
+/// Every `SuiKeyPair` scheme the benchmark can sign with (chunk0-1) must
+/// round-trip: the signature `sign_with_scheme` produces has to verify
+/// against the matching scheme's public key, not just Ed25519's.
+#[test]
+fn sign_with_scheme_round_trips_for_every_key_scheme() {
+    let msg = b"deterministic fixture digest";
+    let mut rng = rand::thread_rng();
+    let key_pairs = vec![
+        SuiKeyPair::Ed25519SuiKeyPair(Ed25519KeyPair::generate(&mut rng)),
+        SuiKeyPair::Secp256k1SuiKeyPair(Secp256k1KeyPair::generate(&mut rng)),
+        SuiKeyPair::Secp256r1SuiKeyPair(Secp256r1KeyPair::generate(&mut rng)),
+    ];
+
+    for key_pair in key_pairs {
+        let public_key = key_pair.public();
+        let signature = sign_with_scheme(&key_pair, msg);
+        public_key
+            .verify(msg, &signature)
+            .expect("signature must verify against the matching scheme's verifier");
+    }
+}